@@ -1,9 +1,18 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+use anchor_lang::system_program::{self, Transfer};
 
 // 这是你的程序ID，部署时会自动生成
 // 暂时使用占位符，实际使用时会被替换
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
+/// label 的最大长度（字节），同时也是单个 PDA 种子的上限
+const MAX_LABEL_LEN: usize = 32;
+
+/// 计数器的业务上限，而非仅依赖 u64 溢出做边界：`increment_by` 与 `reset` 会校验它；
+/// `increment` 不校验此上限，只防止 u64 溢出
+const MAX_COUNT: u64 = 1_000_000;
+
 #[program]
 pub mod counter {
     use super::*;
@@ -16,10 +25,22 @@ pub mod counter {
     /// 
     /// 参数:
     /// - ctx: 包含所有需要的账户信息
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>, label: String) -> Result<()> {
+        // label 过长会超出 PDA 种子上限，也会超出分配的空间
+        require!(label.len() <= MAX_LABEL_LEN, ErrorCode::LabelTooLong);
         let counter = &mut ctx.accounts.counter;
         counter.count = 0;
-        msg!("计数器已初始化，count = 0");
+        // 记录创建者作为 authority，后续变更指令据此做权限校验
+        counter.authority = ctx.accounts.user.key();
+        // 保存 bump，增量指令可直接用它验证 PDA，省去每次重新推导的计算开销
+        counter.bump = ctx.bumps.counter;
+        // 保存 label，便于客户端区分同一用户下的多个计数器
+        counter.label = label;
+        msg!(
+            "计数器 \"{}\" 已初始化，count = 0，authority = {}",
+            counter.label,
+            counter.authority
+        );
         Ok(())
     }
 
@@ -32,8 +53,14 @@ pub mod counter {
     /// 
     /// 参数:
     /// - ctx: 包含所有需要的账户信息
-    pub fn increment(ctx: Context<Increment>) -> Result<()> {
+    pub fn increment(ctx: Context<Increment>, _label: String) -> Result<()> {
         let counter = &mut ctx.accounts.counter;
+        // 只有记录在案的 authority 才能修改计数器
+        require_keys_eq!(
+            ctx.accounts.user.key(),
+            counter.authority,
+            ErrorCode::Unauthorized
+        );
         // 使用 checked_add 防止溢出，如果溢出会返回错误
         counter.count = counter
             .count
@@ -42,26 +69,222 @@ pub mod counter {
         msg!("计数器递增，当前 count = {}", counter.count);
         Ok(())
     }
+
+    /// Increment_by 指令 - 将计数器一次性加上指定数量
+    ///
+    /// 相比多次发送 `increment`，客户端可以在一笔交易里批量增加。
+    ///
+    /// 参数:
+    /// - ctx: 包含所有需要的账户信息
+    /// - amount: 要增加的数量
+    pub fn increment_by(ctx: Context<Increment>, _label: String, amount: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        // 只有记录在案的 authority 才能修改计数器
+        require_keys_eq!(
+            ctx.accounts.user.key(),
+            counter.authority,
+            ErrorCode::Unauthorized
+        );
+        // 先做业务校验：增加后不得超过程序设定的上限
+        let new_count = counter
+            .count
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(new_count <= MAX_COUNT, ErrorCode::ValueOutOfRange);
+        counter.count = new_count;
+        msg!("计数器增加 {}，当前 count = {}", amount, counter.count);
+        Ok(())
+    }
+
+    /// Decrement 指令 - 将计数器减1
+    ///
+    /// 这个指令会：
+    /// 1. 检查账户的有效性与拥有者权限
+    /// 2. 将 count 值减 1
+    /// 3. 使用 checked_sub 防止下溢（低于 0）
+    ///
+    /// 参数:
+    /// - ctx: 包含所有需要的账户信息
+    pub fn decrement(ctx: Context<Increment>, _label: String) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        // 只有记录在案的 authority 才能修改计数器
+        require_keys_eq!(
+            ctx.accounts.user.key(),
+            counter.authority,
+            ErrorCode::Unauthorized
+        );
+        // 使用 checked_sub 防止下溢，如果结果会低于 0 则返回错误
+        counter.count = counter
+            .count
+            .checked_sub(1)
+            .ok_or(ErrorCode::Underflow)?;
+        msg!("计数器递减，当前 count = {}", counter.count);
+        Ok(())
+    }
+
+    /// Decrement_by 指令 - 将计数器一次性减去指定数量
+    ///
+    /// 参数:
+    /// - ctx: 包含所有需要的账户信息
+    /// - amount: 要减少的数量
+    pub fn decrement_by(ctx: Context<Increment>, _label: String, amount: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        // 只有记录在案的 authority 才能修改计数器
+        require_keys_eq!(
+            ctx.accounts.user.key(),
+            counter.authority,
+            ErrorCode::Unauthorized
+        );
+        // 使用 checked_sub 防止下溢，如果结果会低于 0 则返回错误
+        counter.count = counter
+            .count
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Underflow)?;
+        msg!("计数器减少 {}，当前 count = {}", amount, counter.count);
+        Ok(())
+    }
+
+    /// Reset 指令 - 将计数器重置为调用者指定的起始值
+    ///
+    /// 这个指令会：
+    /// 1. 校验调用者是计数器的 authority
+    /// 2. 拒绝超过程序上限的起始值（默认可传 0）
+    /// 3. 把 count 设为 start
+    ///
+    /// 参数:
+    /// - ctx: 包含所有需要的账户信息
+    /// - start: 重置后的起始值
+    pub fn reset(ctx: Context<Increment>, _label: String, start: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        // 只有记录在案的 authority 才能重置计数器
+        require_keys_eq!(
+            ctx.accounts.user.key(),
+            counter.authority,
+            ErrorCode::Unauthorized
+        );
+        // 起始值不得超过程序设定的上限
+        require!(start <= MAX_COUNT, ErrorCode::ValueOutOfRange);
+        counter.count = start;
+        msg!("计数器已重置，count = {}", counter.count);
+        Ok(())
+    }
+
+    /// Close 指令 - 关闭计数器并退还租金
+    ///
+    /// Solana 账户的租金豁免储备只有在关闭账户时才能取回，这个指令会：
+    /// 1. 校验调用者是计数器的 authority
+    /// 2. 借助 `close = user` 将账户的 lamports 退回给 user 并清零数据
+    ///
+    /// 参数:
+    /// - ctx: 包含所有需要的账户信息
+    pub fn close(ctx: Context<CloseCounter>, _label: String) -> Result<()> {
+        // 只有记录在案的 authority 才能关闭并取回租金
+        require_keys_eq!(
+            ctx.accounts.user.key(),
+            ctx.accounts.counter.authority,
+            ErrorCode::Unauthorized
+        );
+        msg!("计数器 \"{}\" 已关闭，租金已退还", ctx.accounts.counter.label);
+        Ok(())
+    }
+
+    /// Deposit 指令 - 向 PDA 托管金库转入 lamports
+    ///
+    /// 这个指令演示 PDA 作为程序控制的托管账户（escrow）：
+    /// 1. 首次调用时初始化 `escrow` 记账账户，记录拥有者与 vault 的 bump
+    /// 2. 通过系统程序 CPI 把 lamports 从 user 转入 vault PDA
+    /// 3. 在账户数据里累加托管余额
+    ///
+    /// 参数:
+    /// - ctx: 包含所有需要的账户信息
+    /// - amount: 存入的 lamports 数量
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.authority = ctx.accounts.user.key();
+        escrow.vault_bump = ctx.bumps.vault;
+
+        // user 自己签名，直接用系统程序 CPI 把 lamports 转入 vault PDA
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        escrow.amount = escrow
+            .amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        msg!("托管存入 {}，当前余额 = {}", amount, escrow.amount);
+        Ok(())
+    }
+
+    /// Withdraw 指令 - 由程序代表 vault PDA 签名释放全部托管金额
+    ///
+    /// 这个指令演示程序派生签名（PDA 的第二个核心用途）：
+    /// 1. 校验调用者是托管账户的 authority
+    /// 2. 用存储的 bump 作为签名种子，通过 `invoke_signed` 让程序代表 vault 签名
+    /// 3. 把托管的 lamports 转回 user，并把记账余额清零
+    ///
+    /// 参数:
+    /// - ctx: 包含所有需要的账户信息
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        // 只有托管账户的拥有者才能取回资金
+        require_keys_eq!(
+            ctx.accounts.user.key(),
+            escrow.authority,
+            ErrorCode::UnauthorizedWithdrawal
+        );
+        let amount = escrow.amount;
+        require!(amount > 0, ErrorCode::InsufficientBalance);
+
+        let user_key = ctx.accounts.user.key();
+        // vault PDA 没有私钥，程序用种子 + bump 代表它签名
+        let signer_seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[escrow.vault_bump]];
+        invoke_signed(
+            &system_instruction::transfer(
+                &ctx.accounts.vault.key(),
+                &user_key,
+                amount,
+            ),
+            &[
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        ctx.accounts.escrow.amount = 0;
+        msg!("托管取回 {}，余额已清零", amount);
+        Ok(())
+    }
 }
 
 // ===== 账户验证结构 =====
 
 /// Initialize 指令需要的账户
 #[derive(Accounts)]
+#[instruction(label: String)]
 pub struct Initialize<'info> {
     /// Counter 账户 - 使用 PDA 派生
     /// 
     /// 关键参数说明：
     /// - init: 表示这是一个需要初始化的新账户
     /// - payer = user: 指定由 user 账户支付创建账户的租金
-    /// - space: 分配的存储空间 = 8字节（discriminator）+ 8字节（u64）
-    /// - seeds: 用于派生 PDA 的种子，这里使用 "counter" + user的公钥
+    /// - space: 分配的存储空间 = 8字节（discriminator）+ 32字节（authority）+ 8字节（count u64）+ 1字节（bump）+ 4字节（label 长度前缀）+ label 最大字节数
+    /// - seeds: 用于派生 PDA 的种子，这里使用 "counter" + user的公钥 + label，从而支持每个用户拥有多个命名计数器
     /// - bump: PDA 的 bump seed，Anchor 会自动找到有效的 bump
     #[account(
         init,
         payer = user,
-        space = 8 + 8,
-        seeds = [b"counter", user.key().as_ref()],
+        space = 8 + 32 + 8 + 1 + 4 + MAX_LABEL_LEN,
+        seeds = [b"counter", user.key().as_ref(), label.as_bytes()],
         bump
     )]
     pub counter: Account<'info, Counter>,
@@ -76,6 +299,7 @@ pub struct Initialize<'info> {
 
 /// Increment 指令需要的账户
 #[derive(Accounts)]
+#[instruction(label: String)]
 pub struct Increment<'info> {
     /// Counter 账户 - 必须是可变的
     /// 
@@ -85,15 +309,94 @@ pub struct Increment<'info> {
     /// - bump: 验证 PDA 的有效性
     #[account(
         mut,
-        seeds = [b"counter", user.key().as_ref()],
-        bump
+        seeds = [b"counter", user.key().as_ref(), label.as_bytes()],
+        bump = counter.bump
     )]
     pub counter: Account<'info, Counter>,
-    
+
     /// 用户账户 - 需要签名
     pub user: Signer<'info>,
 }
 
+/// Close 指令需要的账户
+#[derive(Accounts)]
+#[instruction(label: String)]
+pub struct CloseCounter<'info> {
+    /// Counter 账户 - 关闭后 lamports 退还给 user
+    ///
+    /// 关键参数说明：
+    /// - mut: 关闭账户需要修改其数据和 lamports
+    /// - close = user: Anchor 将账户 lamports 全部转回 user 并清零数据
+    /// - seeds/bump: 校验这是正确的 PDA
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"counter", user.key().as_ref(), label.as_bytes()],
+        bump = counter.bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// 用户账户 - 需要签名，并接收退还的租金
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+/// Deposit 指令需要的账户
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    /// Escrow 记账账户 - 首次存入时创建，记录拥有者与余额
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"escrow", user.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Vault PDA - 仅持有 lamports 的系统账户，由程序派生签名控制
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// 用户账户 - 需要签名并支付转入的 lamports
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 系统程序 - 用于转账与创建账户
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw 指令需要的账户
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    /// Escrow 记账账户 - 校验拥有者并读取余额
+    #[account(
+        mut,
+        seeds = [b"escrow", user.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Vault PDA - 托管 lamports 的来源，由程序代表其签名
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// 用户账户 - 需要签名，并接收取回的 lamports
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// 系统程序 - 用于转账
+    pub system_program: Program<'info, System>,
+}
+
 // ===== 数据结构 =====
 
 /// Counter 账户的数据结构
@@ -101,9 +404,29 @@ pub struct Increment<'info> {
 /// 这个结构定义了存储在区块链上的数据格式
 #[account]
 pub struct Counter {
+    /// 计数器的拥有者，只有它能修改计数器
+    pub authority: Pubkey,
     /// 计数器的值
     /// 使用 u64 类型，范围 0 到 18,446,744,073,709,551,615
     pub count: u64,
+    /// PDA 的 bump seed，保存后无需每次重新推导
+    pub bump: u8,
+    /// 计数器的名称，作为种子的一部分，使同一用户可拥有多个命名计数器
+    pub label: String,
+}
+
+/// Escrow 账户的数据结构
+///
+/// 记录托管金库的拥有者、已托管余额以及 vault PDA 的 bump，
+/// bump 用于程序派生签名（invoke_signed）释放资金。
+#[account]
+pub struct Escrow {
+    /// 托管资金的拥有者，只有它能取回
+    pub authority: Pubkey,
+    /// 当前托管的 lamports 数量
+    pub amount: u64,
+    /// vault PDA 的 bump seed
+    pub vault_bump: u8,
 }
 
 // ===== 错误定义 =====
@@ -113,4 +436,16 @@ pub struct Counter {
 pub enum ErrorCode {
     #[msg("计数器溢出")]
     Overflow,
+    #[msg("无权操作该计数器")]
+    Unauthorized,
+    #[msg("计数器下溢")]
+    Underflow,
+    #[msg("label 过长")]
+    LabelTooLong,
+    #[msg("托管余额不足")]
+    InsufficientBalance,
+    #[msg("无权取回托管资金")]
+    UnauthorizedWithdrawal,
+    #[msg("数值超出允许范围")]
+    ValueOutOfRange,
 }